@@ -1,5 +1,7 @@
 use std::time::Duration;
 
+use arducam_tof::export::{colorize, Colormap};
+use arducam_tof::CameraIntrinsics;
 use bincode::Options;
 use serde::Serialize;
 
@@ -31,44 +33,34 @@ fn main() {
         let frame = cam.request_frame(Some(Duration::from_millis(200))).unwrap();
 
         let depth = frame.get_depth_data();
-
-        let confidence = frame.get_confidence_data();
-
-        assert!(depth.width() == confidence.width());
-        assert!(depth.height() == confidence.height());
-
-        let pixels = depth
-            .as_slice()
-            .iter()
-            .enumerate()
-            .zip(confidence.as_slice())
-            .map(|((i, d), c)| (i % depth.width() as usize, i / depth.width() as usize, d, c));
-
-        let fx = depth.width() as f32 / (2.0 * f32::tan(0.5 * std::f32::consts::PI * 64.3 / 180.0)); // 640 / 2 / tan(0.5*64.3)
-        let fy =
-            depth.height() as f32 / (2.0 * f32::tan(0.5 * std::f32::consts::PI * 50.4 / 180.0)); // 480 / 2 / tan(0.5*50.4)
+        let depth_height = depth.height();
+        let depth_width = depth.width();
+        // `colorize` yields RGB, but OpenCV `Mat`/`imshow` treat 3-channel data as BGR, so swap
+        // the outer channels to keep the palette's perceptual order on screen.
+        let colored: Vec<opencv::core::Vec3b> =
+            colorize(&depth, 0.0, 4000.0, Colormap::Turbo)
+                .into_iter()
+                .map(|[r, g, b]| opencv::core::Vec3b::from([b, g, r]))
+                .collect();
 
         points.clear();
-
-        for (row, column, d, c) in pixels {
-            let z = *d;
-            let x = (((depth.width() / 2) as f32 - column as f32) / fx) * z;
-            let y = (((depth.height() / 2) as f32 - row as f32) / fy) * z;
-
-            points.push(MyPoint {
-                x,
-                y,
-                z,
-                confidence: *c,
-            });
-        }
+        points.extend(
+            frame
+                .point_cloud_iter(&CameraIntrinsics::ARDUCAM_TOF, None)
+                .map(|point| MyPoint {
+                    x: point.x,
+                    y: point.y,
+                    z: point.z,
+                    confidence: point.confidence,
+                }),
+        );
 
         points.serialize(&mut stream).unwrap();
 
         let depth_mat = opencv::core::Mat::new_rows_cols_with_data(
-            depth.height() as i32,
-            depth.width() as i32,
-            depth.as_slice(),
+            depth_height as i32,
+            depth_width as i32,
+            &colored,
         )
         .unwrap();
 
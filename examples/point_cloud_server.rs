@@ -3,8 +3,12 @@ extern crate nalgebra as na;
 
 use std::io::{BufRead, BufReader, Write};
 use std::ops::RangeInclusive;
+use std::path::PathBuf;
 use std::sync::mpsc::{Receiver, Sender, TryRecvError};
 
+use arducam_tof::export::point_cloud::{ColoredPoint, Encoding};
+use arducam_tof::export::Colormap;
+
 use bincode::{DefaultOptions, Options};
 use kiss3d::camera::Camera;
 use kiss3d::context::Context;
@@ -16,7 +20,7 @@ use kiss3d::resource::{
 };
 use kiss3d::text::Font;
 use kiss3d::window::{State, Window};
-use na::{Matrix4, Point2, Point3};
+use na::{Matrix4, Point2, Point3, Vector3};
 use serde::Deserialize;
 
 // Custom renderers are used to allow rendering objects that are not necessarily
@@ -42,6 +46,8 @@ struct AppState {
     max_depth: Option<f32>,
     min_depth: Option<f32>,
     confidence_range: Option<RangeInclusive<f32>>,
+    /// The most recent filtered point set, kept so it can be saved on demand.
+    last_points: Vec<ColoredPoint>,
 }
 
 impl State for AppState {
@@ -65,6 +71,14 @@ impl State for AppState {
             Ok(Command::SetConfidenceRange(confidence_range)) => {
                 self.confidence_range = confidence_range
             }
+            Ok(Command::SetPointRadius(radius)) => {
+                self.point_cloud_renderer.set_point_radius(radius)
+            }
+            Ok(Command::SaveFrame(path)) => {
+                if let Err(e) = save_frame(&self.last_points, &path) {
+                    println!("Failed to save frame: {e}");
+                }
+            }
             Err(TryRecvError::Empty) => (),
             Err(TryRecvError::Disconnected) => std::process::exit(2),
         }
@@ -72,6 +86,7 @@ impl State for AppState {
         match self.point_receiver.try_recv() {
             Ok(points) => {
                 self.point_cloud_renderer.clear();
+                self.last_points.clear();
                 for point in points {
                     if self.max_depth.is_some_and(|max_depth| point.z > max_depth)
                         || self.min_depth.is_some_and(|min_depth| point.z < min_depth)
@@ -104,11 +119,36 @@ impl State for AppState {
                                 }
                             }
                         }
-                        None => Point3::new(1.0, 1.0, 1.0),
+                        // With no confidence range set, fall back to colouring by depth using
+                        // the same turbo map as the 2D preview, normalised over the min/max depth
+                        // clips. Unclipped ends use the preview's fixed 0..4000mm range; the
+                        // preview itself always uses that fixed range, not these clips.
+                        None => {
+                            let min = self.min_depth.unwrap_or(0.0);
+                            let max = self.max_depth.unwrap_or(4000.0);
+                            let t = if max > min {
+                                ((point.z - min) / (max - min)).clamp(0.0, 1.0)
+                            } else {
+                                0.0
+                            };
+                            let [r, g, b] = Colormap::Turbo.lookup(t);
+                            Point3::new(r as f32 / 255.0, g as f32 / 255.0, b as f32 / 255.0)
+                        }
                     };
 
                     self.point_cloud_renderer
                         .push(Point3::new(point.x, point.y, point.z), colour);
+
+                    self.last_points.push(ColoredPoint {
+                        x: point.x,
+                        y: point.y,
+                        z: point.z,
+                        rgb: [
+                            (colour.x * 255.0).round() as u8,
+                            (colour.y * 255.0).round() as u8,
+                            (colour.z * 255.0).round() as u8,
+                        ],
+                    });
                 }
             }
             Err(TryRecvError::Empty) => (),
@@ -140,43 +180,74 @@ fn main() {
 
     let window = Window::new("Kiss3d: persistent_point_cloud");
     let app = AppState {
-        point_cloud_renderer: PointCloudRenderer::new(4.0),
+        // Positions are in millimetres, so the radius is too: ~20mm keeps points visible without
+        // blotting the cloud (the old `4.0` was a pixel size and renders as near-invisible here).
+        point_cloud_renderer: PointCloudRenderer::new(20.0),
         point_receiver,
         command_receiver,
         max_depth: None,
         min_depth: None,
         confidence_range: None,
+        last_points: Vec::new(),
     };
 
     window.render_loop(app)
 }
 
 /// Structure which manages the display of long-living points.
+///
+/// Each point is drawn as a camera-facing quad with a configurable world-space radius, so the
+/// cloud reads as a surface and scales correctly with zoom (unlike a fixed `gl_PointSize`). The
+/// quad is expanded in the vertex shader and the whole cloud is drawn with a single instanced
+/// call: the position/colour buffer supplies one instance per point, and a static four-vertex
+/// quad supplies the corner offsets.
 struct PointCloudRenderer {
     shader: Effect,
     pos: ShaderAttribute<Point3<f32>>,
     color: ShaderAttribute<Point3<f32>>,
+    corner: ShaderAttribute<Point2<f32>>,
     proj: ShaderUniform<Matrix4<f32>>,
     view: ShaderUniform<Matrix4<f32>>,
+    right: ShaderUniform<Vector3<f32>>,
+    up: ShaderUniform<Vector3<f32>>,
+    radius: ShaderUniform<f32>,
     colored_points: GPUVec<Point3<f32>>,
-    point_size: f32,
+    quad: GPUVec<Point2<f32>>,
+    point_radius: f32,
 }
 
 impl PointCloudRenderer {
-    /// Creates a new points renderer.
-    fn new(point_size: f32) -> PointCloudRenderer {
+    /// Creates a new points renderer drawing quads of the given world-space radius.
+    fn new(point_radius: f32) -> PointCloudRenderer {
         let mut shader = Effect::new_from_str(VERTEX_SHADER_SRC, FRAGMENT_SHADER_SRC);
 
         shader.use_program();
 
+        // A unit quad as a triangle strip, expanded per instance in the vertex shader.
+        let quad = GPUVec::new(
+            vec![
+                Point2::new(-1.0, -1.0),
+                Point2::new(1.0, -1.0),
+                Point2::new(-1.0, 1.0),
+                Point2::new(1.0, 1.0),
+            ],
+            BufferType::Array,
+            AllocationType::StaticDraw,
+        );
+
         PointCloudRenderer {
             colored_points: GPUVec::new(Vec::new(), BufferType::Array, AllocationType::StreamDraw),
+            quad,
             pos: shader.get_attrib::<Point3<f32>>("position").unwrap(),
             color: shader.get_attrib::<Point3<f32>>("color").unwrap(),
+            corner: shader.get_attrib::<Point2<f32>>("corner").unwrap(),
             proj: shader.get_uniform::<Matrix4<f32>>("proj").unwrap(),
             view: shader.get_uniform::<Matrix4<f32>>("view").unwrap(),
+            right: shader.get_uniform::<Vector3<f32>>("right").unwrap(),
+            up: shader.get_uniform::<Vector3<f32>>("up").unwrap(),
+            radius: shader.get_uniform::<f32>("radius").unwrap(),
             shader,
-            point_size,
+            point_radius,
         }
     }
 
@@ -196,41 +267,73 @@ impl PointCloudRenderer {
             points.clear();
         }
     }
+
+    /// Sets the world-space radius used to splat each point.
+    fn set_point_radius(&mut self, point_radius: f32) {
+        self.point_radius = point_radius;
+    }
 }
 
 impl Renderer for PointCloudRenderer {
     /// Actually draws the points.
     fn render(&mut self, pass: usize, camera: &mut dyn Camera) {
-        if self.colored_points.len() == 0 {
+        let num_points = self.colored_points.len() / 2;
+        if num_points == 0 {
             return;
         }
 
         self.shader.use_program();
         self.pos.enable();
         self.color.enable();
+        self.corner.enable();
 
         camera.upload(pass, &mut self.proj, &mut self.view);
 
-        self.color.bind_sub_buffer(&mut self.colored_points, 1, 1);
-        self.pos.bind_sub_buffer(&mut self.colored_points, 1, 0);
+        // The camera's right/up axes in world space are the first two columns of the inverse
+        // view transform; the vertex shader expands each point along them.
+        let inv = camera.view_transform().inverse().to_homogeneous();
+        self.right
+            .upload(&Vector3::new(inv[(0, 0)], inv[(1, 0)], inv[(2, 0)]));
+        self.up
+            .upload(&Vector3::new(inv[(0, 1)], inv[(1, 1)], inv[(2, 1)]));
+        self.radius.upload(&self.point_radius);
 
         let ctxt = Context::get();
-        ctxt.point_size(self.point_size);
-        ctxt.draw_arrays(Context::POINTS, 0, (self.colored_points.len() / 2) as i32);
+
+        // The quad corners are shared across instances; position and colour advance once per
+        // instance (interleaved in a single buffer, stride 2, offsets 0 and 1).
+        self.corner.bind(&mut self.quad);
+        ctxt.vertex_attrib_divisor(self.corner.id(), 0);
+        self.pos.bind_sub_buffer(&mut self.colored_points, 1, 0);
+        ctxt.vertex_attrib_divisor(self.pos.id(), 1);
+        self.color.bind_sub_buffer(&mut self.colored_points, 1, 1);
+        ctxt.vertex_attrib_divisor(self.color.id(), 1);
+
+        ctxt.draw_arrays_instanced(Context::TRIANGLE_STRIP, 0, 4, num_points as i32);
+
+        // Reset the per-instance divisors so other renderers aren't affected.
+        ctxt.vertex_attrib_divisor(self.pos.id(), 0);
+        ctxt.vertex_attrib_divisor(self.color.id(), 0);
 
         self.pos.disable();
         self.color.disable();
+        self.corner.disable();
     }
 }
 
 const VERTEX_SHADER_SRC: &str = "#version 100
     attribute vec3 position;
     attribute vec3 color;
+    attribute vec2 corner;
     varying   vec3 Color;
     uniform   mat4 proj;
     uniform   mat4 view;
+    uniform   vec3 right;
+    uniform   vec3 up;
+    uniform   float radius;
     void main() {
-        gl_Position = proj * view * vec4(position, 1.0);
+        vec3 world = position + corner.x * radius * right + corner.y * radius * up;
+        gl_Position = proj * view * vec4(world, 1.0);
         Color = color;
     }";
 
@@ -266,6 +369,24 @@ enum Command {
     SetMaxDepth(Option<f32>),
     SetMinDepth(Option<f32>),
     SetConfidenceRange(Option<RangeInclusive<f32>>),
+    SetPointRadius(f32),
+    SaveFrame(PathBuf),
+}
+
+/// Save the current filtered point set to PLY or PCD, chosen by the path's extension.
+fn save_frame(points: &[ColoredPoint], path: &std::path::Path) -> Result<(), std::io::Error> {
+    match path.extension().and_then(|ext| ext.to_str()) {
+        Some("ply") => arducam_tof::export::point_cloud::write_ply(points, Encoding::Ascii, path),
+        Some("pcd") => arducam_tof::export::point_cloud::write_pcd(points, Encoding::Ascii, path),
+        _ => {
+            println!("Unrecognised extension, expected .ply or .pcd");
+            return Ok(());
+        }
+    }
+    .map_err(|e| match e {
+        arducam_tof::export::ExportError::Io(e) => e,
+        other => std::io::Error::other(other.to_string()),
+    })
 }
 
 fn control_thread(sender: Sender<Command>) {
@@ -298,6 +419,19 @@ fn control_thread(sender: Sender<Command>) {
                     Err(e) => println!("{e}"),
                 }
             }
+            input if input.starts_with("save frame ") => {
+                let path = input.strip_prefix("save frame ").unwrap();
+                sender
+                    .send(Command::SaveFrame(PathBuf::from(path)))
+                    .unwrap()
+            }
+            input if input.starts_with("set point radius ") => {
+                let radius = input.strip_prefix("set point radius ").unwrap();
+                match radius.parse::<f32>() {
+                    Ok(radius) => sender.send(Command::SetPointRadius(radius)).unwrap(),
+                    Err(e) => println!("{e}"),
+                }
+            }
             input if input.starts_with("set confidence range ") => {
                 let range = input.strip_prefix("set confidence range ").unwrap();
                 let Some((low, high)) = range.split_once(' ') else {
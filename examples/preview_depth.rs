@@ -1,5 +1,8 @@
 use std::time::Duration;
 
+use arducam_tof::export::{colorize, Colormap};
+use opencv::core::Vec3b;
+
 fn main() {
     let mut cam = arducam_tof::ArducamDepthCamera::new().unwrap();
     cam.open(arducam_tof::Connection::CSI, 0).unwrap();
@@ -7,14 +10,29 @@ fn main() {
 
     opencv::highgui::named_window("depth", opencv::highgui::WINDOW_NORMAL).unwrap();
 
+    // Map depth over the sensor's 4m range to a perceptually ordered palette, which is far
+    // easier to read than the near-flat grayscale the raw floats render as.
+    let (min, max) = (0.0, 4000.0);
+
     loop {
         let frame = cam.request_frame(Some(Duration::from_millis(200))).unwrap();
 
         let depth = frame.get_depth_data();
 
-        let depth_mat = opencv::core::Mat::new_rows_cols_with_data(depth.height() as i32, depth.width() as i32, depth.as_slice()).unwrap();
+        // `colorize` yields RGB, but OpenCV `Mat`/`imshow` treat 3-channel data as BGR, so swap
+        // the outer channels to keep the palette's perceptual order on screen.
+        let colored: Vec<Vec3b> = colorize(&depth, min, max, Colormap::Turbo)
+            .into_iter()
+            .map(|[r, g, b]| Vec3b::from([b, g, r]))
+            .collect();
+
+        let depth_mat = opencv::core::Mat::new_rows_cols_with_data(
+            depth.height() as i32,
+            depth.width() as i32,
+            &colored,
+        )
+        .unwrap();
 
         opencv::highgui::imshow("depth", &depth_mat).unwrap();
     }
-
 }
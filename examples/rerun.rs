@@ -0,0 +1,30 @@
+//! Stream depth frames to the Rerun viewer.
+//!
+//! Run with `cargo run --example rerun --features rerun`. Each frame is logged as a `DepthImage`
+//! under a `Pinhole` transform carrying the sensor intrinsics, so the viewer performs the
+//! backprojection and lets you clip the depth range interactively — replacing the hand-rolled
+//! TCP/bincode + kiss3d stack with time-scrubbing, multi-stream sync, and remote viewing.
+
+use std::time::Duration;
+
+use arducam_tof::{log_pinhole_to_rerun, ArducamDepthCamera, Connection, FrameType};
+
+fn main() {
+    let rec = rerun::RecordingStreamBuilder::new("arducam_tof")
+        .spawn()
+        .unwrap();
+
+    let mut cam = ArducamDepthCamera::new().unwrap();
+    cam.open(Connection::CSI, 0).unwrap();
+    cam.start(FrameType::DepthFrame).unwrap();
+
+    let intrinsics = cam.intrinsics();
+
+    loop {
+        let frame = cam.request_frame(Some(Duration::from_millis(200))).unwrap();
+        let depth = frame.get_depth_data();
+
+        log_pinhole_to_rerun(&rec, "camera", &intrinsics, depth.width(), depth.height()).unwrap();
+        frame.log_depth_to_rerun(&rec, "camera/depth").unwrap();
+    }
+}
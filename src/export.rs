@@ -0,0 +1,179 @@
+//! Persisting frames to disk: 16-bit depth PNGs, 8-bit confidence PNGs, a self-describing raw
+//! dump, and a colorized preview.
+//!
+//! This turns the crate into something usable for dataset collection and offline replay, rather
+//! than only live viewing.
+
+use std::io::{BufWriter, Write};
+use std::path::Path;
+
+use thiserror::Error;
+
+use crate::{FrameData, FrameType};
+
+pub mod dynamic_depth;
+pub mod point_cloud;
+
+/// Magic bytes at the start of a raw dump, identifying the format.
+const RAW_MAGIC: [u8; 4] = *b"ATOF";
+
+#[derive(Debug, Error)]
+/// Returned when an [export](crate::export) operation fails.
+pub enum ExportError {
+    /// An underlying IO operation failed.
+    #[error("io error: {0}")]
+    Io(#[from] std::io::Error),
+    /// The PNG encoder rejected the frame.
+    #[error("png encoding error: {0}")]
+    Png(#[from] png::EncodingError),
+}
+
+/// Write a depth frame to a 16-bit grayscale PNG, in millimetres.
+///
+/// Each depth value is multiplied by `scale` and rounded to the nearest `u16`, so a `scale` of
+/// `1.0` stores raw millimetres. The scale factor is recorded in a `depth_scale` text chunk so
+/// the original metric values can be recovered.
+pub fn write_depth_png(
+    frame: &FrameData<f32>,
+    scale: f32,
+    path: impl AsRef<Path>,
+) -> Result<(), ExportError> {
+    let mut data = Vec::with_capacity(frame.as_slice().len() * 2);
+    for &depth in frame.as_slice() {
+        let value = (depth * scale).round().clamp(0.0, u16::MAX as f32) as u16;
+        data.extend_from_slice(&value.to_be_bytes());
+    }
+
+    let file = BufWriter::new(std::fs::File::create(path)?);
+    let mut encoder = png::Encoder::new(file, frame.width() as u32, frame.height() as u32);
+    encoder.set_color(png::ColorType::Grayscale);
+    encoder.set_depth(png::BitDepth::Sixteen);
+    encoder.add_text_chunk("depth_scale".to_string(), scale.to_string())?;
+    encoder.write_header()?.write_image_data(&data)?;
+    Ok(())
+}
+
+/// Write a confidence frame to an 8-bit grayscale PNG.
+///
+/// Confidence values are clamped into `0..=255`.
+pub fn write_confidence_png(
+    frame: &FrameData<f32>,
+    path: impl AsRef<Path>,
+) -> Result<(), ExportError> {
+    let data: Vec<u8> = frame
+        .as_slice()
+        .iter()
+        .map(|c| c.clamp(0.0, u8::MAX as f32) as u8)
+        .collect();
+
+    let file = BufWriter::new(std::fs::File::create(path)?);
+    let mut encoder = png::Encoder::new(file, frame.width() as u32, frame.height() as u32);
+    encoder.set_color(png::ColorType::Grayscale);
+    encoder.set_depth(png::BitDepth::Eight);
+    encoder.write_header()?.write_image_data(&data)?;
+    Ok(())
+}
+
+/// Write a frame to a tightly-packed raw `.bin` with a small header so captures can be replayed
+/// offline.
+///
+/// The layout is the [magic](RAW_MAGIC) bytes, then little-endian `width: u16`, `height: u16`,
+/// `frame_type: u8`, `timestamp: u64`, followed by the row-major `f32` samples in little-endian
+/// order.
+pub fn write_raw(
+    frame: &FrameData<f32>,
+    frame_type: FrameType,
+    timestamp: u64,
+    path: impl AsRef<Path>,
+) -> Result<(), ExportError> {
+    let mut writer = BufWriter::new(std::fs::File::create(path)?);
+    writer.write_all(&RAW_MAGIC)?;
+    writer.write_all(&frame.width().to_le_bytes())?;
+    writer.write_all(&frame.height().to_le_bytes())?;
+    writer.write_all(&[raw_frame_type(frame_type)])?;
+    writer.write_all(&timestamp.to_le_bytes())?;
+    for &sample in frame.as_slice() {
+        writer.write_all(&sample.to_le_bytes())?;
+    }
+    writer.flush()?;
+    Ok(())
+}
+
+/// The byte written into a raw dump header for each [FrameType].
+fn raw_frame_type(frame_type: FrameType) -> u8 {
+    match frame_type {
+        FrameType::RawFrame => 0,
+        FrameType::ConfidenceFrame => 1,
+        FrameType::DepthFrame => 2,
+    }
+}
+
+/// A perceptually ordered colour map for depth previews.
+#[derive(Debug, Clone, Copy)]
+pub enum Colormap {
+    /// Google's "turbo" map, a perceptually improved rainbow.
+    Turbo,
+    /// The classic "jet" rainbow map.
+    Jet,
+}
+
+impl Colormap {
+    /// Map a normalised value in `0.0..=1.0` to an RGB triple.
+    pub fn lookup(self, t: f32) -> [u8; 3] {
+        let t = t.clamp(0.0, 1.0);
+        match self {
+            Colormap::Turbo => turbo(t),
+            Colormap::Jet => jet(t),
+        }
+    }
+}
+
+/// Map a depth frame to a packed RGB image via `colormap`, normalising depth over `[min, max]`.
+///
+/// The result is ready to hand to, e.g., OpenCV's `Mat::new_rows_cols_with_data` as a 3-channel
+/// image. Depths outside the range are clamped to the end colours.
+pub fn colorize(frame: &FrameData<f32>, min: f32, max: f32, colormap: Colormap) -> Vec<[u8; 3]> {
+    let span = max - min;
+    frame
+        .as_slice()
+        .iter()
+        .map(|&depth| {
+            let t = if span > 0.0 { (depth - min) / span } else { 0.0 };
+            colormap.lookup(t)
+        })
+        .collect()
+}
+
+/// Google's turbo colormap, via the polynomial approximation published alongside it.
+fn turbo(t: f32) -> [u8; 3] {
+    // Coefficients from the turbo approximation (Mikhail Matrosov / Google AI).
+    const R: [f32; 6] = [0.13572138, 4.61539260, -42.66032258, 132.13108234, -152.94239396, 59.28637943];
+    const G: [f32; 6] = [0.09140261, 2.19418839, 4.84296658, -14.18503333, 4.27729857, 2.82956604];
+    const B: [f32; 6] = [0.10667330, 12.64194608, -60.58204836, 110.36276771, -89.90310912, 27.34824973];
+
+    let poly = |c: [f32; 6]| {
+        let t2 = t * t;
+        let t3 = t2 * t;
+        let t4 = t3 * t;
+        let t5 = t4 * t;
+        c[0] + c[1] * t + c[2] * t2 + c[3] * t3 + c[4] * t4 + c[5] * t5
+    };
+
+    [
+        (poly(R).clamp(0.0, 1.0) * 255.0).round() as u8,
+        (poly(G).clamp(0.0, 1.0) * 255.0).round() as u8,
+        (poly(B).clamp(0.0, 1.0) * 255.0).round() as u8,
+    ]
+}
+
+/// The classic jet colormap: blue → cyan → green → yellow → red.
+fn jet(t: f32) -> [u8; 3] {
+    let r = (1.5 - (4.0 * t - 3.0).abs()).clamp(0.0, 1.0);
+    let g = (1.5 - (4.0 * t - 2.0).abs()).clamp(0.0, 1.0);
+    let b = (1.5 - (4.0 * t - 1.0).abs()).clamp(0.0, 1.0);
+    [
+        (r * 255.0).round() as u8,
+        (g * 255.0).round() as u8,
+        (b * 255.0).round() as u8,
+    ]
+}
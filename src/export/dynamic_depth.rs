@@ -0,0 +1,177 @@
+//! Export a depth + confidence pair as a [Dynamic Depth] container.
+//!
+//! The confidence frame is written as the primary JPEG and the depth map as a secondary image
+//! concatenated after it, with an XMP packet (using the GContainer / GDepth namespaces) recording
+//! the near/far range, depth units, and camera intrinsics. This makes Arducam captures readable
+//! by tools that already consume the Dynamic Depth format.
+//!
+//! [Dynamic Depth]: https://developer.android.com/media/platform/dynamic-depth
+
+use std::io::Write;
+use std::path::Path;
+
+use jpeg_encoder::{ColorType, Encoder as JpegEncoder};
+
+use crate::{CameraIntrinsics, FrameData};
+
+use super::ExportError;
+
+/// How the metric depth map is quantised into the secondary image.
+#[derive(Debug, Clone, Copy)]
+pub enum Quantization {
+    /// Linear across the range: `v = (d − near) / (far − near)`.
+    RangeLinear,
+    /// Inverse across the range, giving more precision to near depths:
+    /// `v = far·(d − near) / (d·(far − near))`.
+    RangeInverse,
+}
+
+impl Quantization {
+    /// The XMP `GDepth:Format` token for this quantisation.
+    fn format_token(self) -> &'static str {
+        match self {
+            Quantization::RangeLinear => "RangeLinear",
+            Quantization::RangeInverse => "RangeInverse",
+        }
+    }
+
+    /// Normalise a metric depth `d` (millimetres) into `0.0..=1.0` over `[near, far]`.
+    fn normalize(self, d: f32, near: f32, far: f32) -> f32 {
+        let v = match self {
+            Quantization::RangeLinear => (d - near) / (far - near),
+            Quantization::RangeInverse => {
+                if d <= 0.0 {
+                    0.0
+                } else {
+                    (far * (d - near)) / (d * (far - near))
+                }
+            }
+        };
+        v.clamp(0.0, 1.0)
+    }
+}
+
+/// The near/far working range of a Dynamic Depth export, in millimetres.
+#[derive(Debug, Clone, Copy)]
+pub struct DepthRange {
+    /// The nearest depth the map resolves.
+    pub near: f32,
+    /// The furthest depth the map resolves.
+    pub far: f32,
+}
+
+/// Write a [Dynamic Depth](self) container for `depth`/`confidence` to `path`.
+///
+/// `confidence` becomes the primary JPEG; `depth` is quantised per `quantization` over `range`
+/// into an 8-bit grayscale JPEG appended as the secondary image. `intrinsics` are recorded in the
+/// XMP so downstream tools can reproject.
+pub fn write_dynamic_depth(
+    depth: &FrameData<f32>,
+    confidence: &FrameData<f32>,
+    intrinsics: &CameraIntrinsics,
+    range: DepthRange,
+    quantization: Quantization,
+    path: impl AsRef<Path>,
+) -> Result<(), ExportError> {
+    // Primary image: the confidence frame as a grayscale JPEG.
+    let confidence_px: Vec<u8> = confidence
+        .as_slice()
+        .iter()
+        .map(|c| c.clamp(0.0, u8::MAX as f32) as u8)
+        .collect();
+    let primary = encode_jpeg(&confidence_px, confidence.width(), confidence.height())?;
+
+    // Secondary image: the quantised depth map as a grayscale JPEG.
+    let depth_px: Vec<u8> = depth
+        .as_slice()
+        .iter()
+        .map(|&d| (quantization.normalize(d, range.near, range.far) * 255.0).round() as u8)
+        .collect();
+    let secondary = encode_jpeg(&depth_px, depth.width(), depth.height())?;
+
+    let xmp = build_xmp(intrinsics, depth, range, quantization, secondary.len());
+    let primary = insert_xmp(&primary, &xmp);
+
+    let mut writer = std::io::BufWriter::new(std::fs::File::create(path)?);
+    writer.write_all(&primary)?;
+    writer.write_all(&secondary)?;
+    writer.flush()?;
+    Ok(())
+}
+
+/// Encode a single-channel buffer as a baseline grayscale JPEG, returning the bytes.
+fn encode_jpeg(pixels: &[u8], width: u16, height: u16) -> Result<Vec<u8>, ExportError> {
+    let mut buffer = Vec::new();
+    let encoder = JpegEncoder::new(&mut buffer, 90);
+    encoder
+        .encode(pixels, width, height, ColorType::Luma)
+        .map_err(|e| ExportError::Io(std::io::Error::other(e.to_string())))?;
+    Ok(buffer)
+}
+
+/// Build the XMP packet describing the embedded depth map and intrinsics.
+fn build_xmp(
+    intrinsics: &CameraIntrinsics,
+    depth: &FrameData<f32>,
+    range: DepthRange,
+    quantization: Quantization,
+    secondary_len: usize,
+) -> String {
+    let (fx, fy) = intrinsics.focal_lengths(depth.width(), depth.height());
+    format!(
+        r#"<x:xmpmeta xmlns:x="adobe:ns:meta/">
+ <rdf:RDF xmlns:rdf="http://www.w3.org/1999/02/22-rdf-syntax-ns#">
+  <rdf:Description
+    xmlns:Container="http://ns.google.com/photos/1.0/container/"
+    xmlns:Item="http://ns.google.com/photos/1.0/container/item/"
+    xmlns:GDepth="http://ns.google.com/photos/1.0/depthmap/"
+    xmlns:GCamera="http://ns.google.com/photos/1.0/camera/"
+    GDepth:Format="{format}"
+    GDepth:Near="{near}"
+    GDepth:Far="{far}"
+    GDepth:Units="millimeters"
+    GCamera:FocalLengthX="{fx}"
+    GCamera:FocalLengthY="{fy}">
+   <Container:Directory>
+    <rdf:Seq>
+     <rdf:li rdf:parseType="Resource">
+      <Container:Item Item:Mime="image/jpeg" Item:Semantic="Primary"/>
+     </rdf:li>
+     <rdf:li rdf:parseType="Resource">
+      <Container:Item Item:Mime="image/jpeg" Item:Semantic="Depth" Item:Length="{len}"/>
+     </rdf:li>
+    </rdf:Seq>
+   </Container:Directory>
+  </rdf:Description>
+ </rdf:RDF>
+</x:xmpmeta>"#,
+        format = quantization.format_token(),
+        near = range.near,
+        far = range.far,
+        fx = fx,
+        fy = fy,
+        len = secondary_len,
+    )
+}
+
+/// Identifier for a standard XMP APP1 segment, including its terminating NUL.
+const XMP_IDENTIFIER: &[u8] = b"http://ns.adobe.com/xap/1.0/\0";
+
+/// Insert an XMP APP1 segment immediately after the SOI marker of a JPEG.
+fn insert_xmp(jpeg: &[u8], xmp: &str) -> Vec<u8> {
+    let payload_len = XMP_IDENTIFIER.len() + xmp.len();
+    // APP1 length field counts itself (2 bytes) plus the payload.
+    let segment_len = (payload_len + 2) as u16;
+
+    let mut out = Vec::with_capacity(jpeg.len() + payload_len + 4);
+    // Copy the SOI marker (FF D8).
+    out.extend_from_slice(&jpeg[..2]);
+    // APP1 marker + length + payload.
+    out.extend_from_slice(&[0xFF, 0xE1]);
+    out.extend_from_slice(&segment_len.to_be_bytes());
+    out.extend_from_slice(XMP_IDENTIFIER);
+    out.extend_from_slice(xmp.as_bytes());
+    // The rest of the original JPEG.
+    out.extend_from_slice(&jpeg[2..]);
+    out
+}
@@ -0,0 +1,137 @@
+//! Writing captured point clouds to the standard PLY and PCD interchange formats.
+//!
+//! Both ASCII and binary-little-endian variants are supported so captures can be taken into
+//! MeshLab, CloudCompare, or PCL/ROS pipelines for offline analysis.
+
+use std::io::{BufWriter, Write};
+use std::path::Path;
+
+use super::ExportError;
+
+/// A single coloured point to be written to a point-cloud file.
+#[derive(Debug, Clone, Copy)]
+pub struct ColoredPoint {
+    /// Horizontal coordinate.
+    pub x: f32,
+    /// Upward coordinate.
+    pub y: f32,
+    /// Forward coordinate.
+    pub z: f32,
+    /// The point's RGB colour.
+    pub rgb: [u8; 3],
+}
+
+/// Whether a point-cloud file is written as text or packed little-endian binary.
+#[derive(Debug, Clone, Copy)]
+pub enum Encoding {
+    /// Human-readable ASCII.
+    Ascii,
+    /// Packed binary, little-endian.
+    BinaryLittleEndian,
+}
+
+/// Write `points` to a PLY file with `x y z` floats and `red green blue` `uchar` properties.
+pub fn write_ply(
+    points: &[ColoredPoint],
+    encoding: Encoding,
+    path: impl AsRef<Path>,
+) -> Result<(), ExportError> {
+    let mut writer = BufWriter::new(std::fs::File::create(path)?);
+
+    let format = match encoding {
+        Encoding::Ascii => "ascii 1.0",
+        Encoding::BinaryLittleEndian => "binary_little_endian 1.0",
+    };
+    write!(
+        writer,
+        "ply\n\
+         format {format}\n\
+         element vertex {count}\n\
+         property float x\n\
+         property float y\n\
+         property float z\n\
+         property uchar red\n\
+         property uchar green\n\
+         property uchar blue\n\
+         end_header\n",
+        count = points.len(),
+    )?;
+
+    match encoding {
+        Encoding::Ascii => {
+            for p in points {
+                writeln!(
+                    writer,
+                    "{} {} {} {} {} {}",
+                    p.x, p.y, p.z, p.rgb[0], p.rgb[1], p.rgb[2]
+                )?;
+            }
+        }
+        Encoding::BinaryLittleEndian => {
+            for p in points {
+                writer.write_all(&p.x.to_le_bytes())?;
+                writer.write_all(&p.y.to_le_bytes())?;
+                writer.write_all(&p.z.to_le_bytes())?;
+                writer.write_all(&p.rgb)?;
+            }
+        }
+    }
+
+    writer.flush()?;
+    Ok(())
+}
+
+/// Write `points` to a PCD file with `x y z rgb` fields, where `rgb` is the packed-float colour
+/// used by ROS `PointCloud2`.
+pub fn write_pcd(
+    points: &[ColoredPoint],
+    encoding: Encoding,
+    path: impl AsRef<Path>,
+) -> Result<(), ExportError> {
+    let mut writer = BufWriter::new(std::fs::File::create(path)?);
+
+    let data = match encoding {
+        Encoding::Ascii => "ascii",
+        Encoding::BinaryLittleEndian => "binary",
+    };
+    write!(
+        writer,
+        "# .PCD v0.7 - Point Cloud Data file format\n\
+         VERSION 0.7\n\
+         FIELDS x y z rgb\n\
+         SIZE 4 4 4 4\n\
+         TYPE F F F F\n\
+         COUNT 1 1 1 1\n\
+         WIDTH {count}\n\
+         HEIGHT 1\n\
+         VIEWPOINT 0 0 0 1 0 0 0\n\
+         POINTS {count}\n\
+         DATA {data}\n",
+        count = points.len(),
+    )?;
+
+    match encoding {
+        Encoding::Ascii => {
+            for p in points {
+                let rgb = f32::from_bits(pack_rgb(p.rgb));
+                writeln!(writer, "{} {} {} {}", p.x, p.y, p.z, rgb)?;
+            }
+        }
+        Encoding::BinaryLittleEndian => {
+            for p in points {
+                writer.write_all(&p.x.to_le_bytes())?;
+                writer.write_all(&p.y.to_le_bytes())?;
+                writer.write_all(&p.z.to_le_bytes())?;
+                writer.write_all(&pack_rgb(p.rgb).to_le_bytes())?;
+            }
+        }
+    }
+
+    writer.flush()?;
+    Ok(())
+}
+
+/// Pack an RGB triple into the `0x00RRGGBB` `u32` that PCL/ROS reinterpret as a float `rgb`.
+fn pack_rgb([r, g, b]: [u8; 3]) -> u32 {
+    (r as u32) << 16 | (g as u32) << 8 | (b as u32)
+}
@@ -1,7 +1,28 @@
-use std::{marker::PhantomData, num::NonZero, ptr::NonNull, time::Duration};
+use std::{
+    marker::PhantomData,
+    num::NonZero,
+    ptr::NonNull,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        mpsc::{channel, sync_channel, Receiver, Sender, TrySendError},
+        Arc,
+    },
+    thread::JoinHandle,
+    time::Duration,
+};
 
 use thiserror::Error;
 
+mod point_cloud;
+pub use point_cloud::{CameraIntrinsics, Point};
+
+pub mod export;
+
+#[cfg(feature = "rerun")]
+mod rerun;
+#[cfg(feature = "rerun")]
+pub use rerun::{log_pinhole_to_rerun, RerunError};
+
 mod raw {
     #![allow(non_upper_case_globals)]
     #![allow(non_camel_case_types)]
@@ -43,6 +64,17 @@ pub struct StartError(NonZero<std::ffi::c_int>);
 #[error("Failed to stop camera, got error code: {0}")]
 pub struct StopError(NonZero<std::ffi::c_int>);
 
+#[derive(Debug, Error)]
+/// Returned when [ArducamDepthCamera::set_control] or [ArducamDepthCamera::get_control] fails
+pub enum ControlError {
+    /// The camera has not been opened yet, so its controls cannot be accessed.
+    #[error("Camera must be opened before accessing controls")]
+    NotOpened,
+    /// The SDK rejected the control access with the contained error code.
+    #[error("Failed to access camera control, got error code: {0}")]
+    Failed(NonZero<std::ffi::c_int>),
+}
+
 impl ArducamDepthCamera {
     pub fn new() -> Result<Self, InitError> {
         let inner = unsafe { raw::createArducamDepthCamera() };
@@ -133,6 +165,205 @@ impl ArducamDepthCamera {
             camera: self.inner,
         })
     }
+
+    /// Write a sensor control, e.g. to switch the distance [Range](Control::Range) or
+    /// drop low-confidence pixels in hardware via [ConfidenceThreshold](Control::ConfidenceThreshold).
+    ///
+    /// The camera must have been [opened](ArducamDepthCamera::open) first. Use
+    /// [Control::range] to validate `value` against the hardware limits before writing.
+    pub fn set_control(&mut self, control: Control, value: i32) -> Result<(), ControlError> {
+        if !self.opened {
+            return Err(ControlError::NotOpened);
+        }
+        let status =
+            unsafe { raw::arducamCameraSetControl(self.inner.as_ptr(), control.into(), value) };
+        match NonZero::new(status) {
+            Some(error) => Err(ControlError::Failed(error)),
+            None => Ok(()),
+        }
+    }
+
+    /// Read back the current value of a sensor control.
+    ///
+    /// The camera must have been [opened](ArducamDepthCamera::open) first.
+    pub fn get_control(&self, control: Control) -> Result<i32, ControlError> {
+        if !self.opened {
+            return Err(ControlError::NotOpened);
+        }
+        let mut value: std::ffi::c_int = 0;
+        let status = unsafe {
+            raw::arducamCameraGetControl(self.inner.as_ptr(), control.into(), &mut value)
+        };
+        match NonZero::new(status) {
+            Some(error) => Err(ControlError::Failed(error)),
+            None => Ok(value as i32),
+        }
+    }
+
+    /// The pinhole intrinsics of the sensor, used to backproject depth frames into 3D points
+    /// (see [ArducamFrameBuffer::point_cloud]).
+    pub fn intrinsics(&self) -> CameraIntrinsics {
+        CameraIntrinsics::ARDUCAM_TOF
+    }
+
+    /// Consume the camera and capture frames on a background thread.
+    ///
+    /// The camera must already be [opened](ArducamDepthCamera::open) and
+    /// [started](ArducamDepthCamera::start). The returned [FrameStream] owns the capture
+    /// thread; the paired [`Receiver<OwnedFrame>`](OwnedFrame) yields [OwnedFrame]s whose
+    /// `depth`/`confidence` buffers are owned copies and so outlive the SDK's internal frame
+    /// buffer. This lets a render or serialization loop keep working while the next frame is
+    /// acquired, instead of holding a borrowing [ArducamFrameBuffer] across the whole loop.
+    ///
+    /// `capacity` bounds the channel so a slow consumer applies backpressure to the capture
+    /// thread rather than growing an unbounded queue. Consumers can return drained frames to
+    /// the thread with [FrameStream::recycle] to avoid per-frame allocation.
+    ///
+    /// `timeout` is passed through to [request_frame](ArducamDepthCamera::request_frame) on each
+    /// capture. Prefer a `Some(_)` timeout: with `None` the capture blocks in the SDK until a
+    /// frame arrives and cannot observe a [stop](FrameStream::stop)/drop request until it does,
+    /// so shutdown only completes once the next frame (or none) is delivered.
+    pub fn into_stream(
+        self,
+        capacity: usize,
+        timeout: Option<Duration>,
+    ) -> (FrameStream, Receiver<OwnedFrame>) {
+        let (frame_tx, frame_rx) = sync_channel::<OwnedFrame>(capacity);
+        let (recycle_tx, recycle_rx) = channel::<(Vec<f32>, Vec<f32>)>();
+        let stop = Arc::new(AtomicBool::new(false));
+
+        let thread_stop = Arc::clone(&stop);
+        let camera = AssertSend(self);
+        let handle = std::thread::spawn(move || {
+            let mut camera = camera.0;
+            'capture: while !thread_stop.load(Ordering::Relaxed) {
+                let frame = match camera.request_frame(timeout) {
+                    Ok(frame) => frame,
+                    // A timeout (or any transient error) just means no frame this round.
+                    Err(_) => continue,
+                };
+
+                let depth = frame.get_depth_data();
+                let confidence = frame.get_confidence_data();
+                let format = frame.get_format(FrameType::DepthFrame);
+
+                let (mut depth_buf, mut confidence_buf) =
+                    recycle_rx.try_recv().unwrap_or_default();
+                depth_buf.clear();
+                depth_buf.extend_from_slice(depth.as_slice());
+                confidence_buf.clear();
+                confidence_buf.extend_from_slice(confidence.as_slice());
+
+                let owned = OwnedFrame {
+                    width: format.width,
+                    height: format.height,
+                    timestamp: format.timestamp,
+                    depth: depth_buf,
+                    confidence: confidence_buf,
+                };
+
+                // Release the SDK buffer before handing the frame to the (bounded) channel.
+                drop(frame);
+
+                // Push onto the bounded channel without parking indefinitely: a blocking `send`
+                // would wedge the thread if the consumer stopped draining without dropping the
+                // `Receiver`, so that `stop()`/drop could never join. Retry while re-checking the
+                // stop flag instead.
+                let mut owned = owned;
+                loop {
+                    match frame_tx.try_send(owned) {
+                        Ok(()) => break,
+                        // Consumer hung up.
+                        Err(TrySendError::Disconnected(_)) => break 'capture,
+                        Err(TrySendError::Full(frame)) => {
+                            if thread_stop.load(Ordering::Relaxed) {
+                                break 'capture;
+                            }
+                            owned = frame;
+                            std::thread::sleep(Duration::from_millis(10));
+                        }
+                    }
+                }
+            }
+            camera
+        });
+
+        let stream = FrameStream {
+            stop,
+            recycle: recycle_tx,
+            handle: Some(handle),
+        };
+
+        (stream, frame_rx)
+    }
+}
+
+/// Wrapper asserting the camera handle may be moved to the capture thread.
+///
+/// The SDK serialises all access through a single owned handle, and [FrameStream] never shares
+/// it across threads, so moving it into the capture thread is sound even though the raw pointer
+/// is not `Send`.
+struct AssertSend(ArducamDepthCamera);
+
+// SAFETY: the wrapped handle is only ever owned by one thread at a time — it is moved into the
+// capture thread and moved back out when the thread joins.
+unsafe impl Send for AssertSend {}
+
+/// An owned, self-contained copy of a single captured frame.
+///
+/// Produced by the capture thread spawned in [ArducamDepthCamera::into_stream]. Unlike
+/// [FrameData], the buffers are owned, so a frame can be held and processed long after the SDK
+/// has recycled its internal buffer for the next capture.
+pub struct OwnedFrame {
+    /// The frame width in pixels.
+    pub width: u16,
+    /// The frame height in pixels.
+    pub height: u16,
+    /// The SDK-reported capture timestamp.
+    pub timestamp: u64,
+    /// Row-major depth values, in millimetres.
+    pub depth: Vec<f32>,
+    /// Row-major confidence values, parallel to [depth](OwnedFrame::depth).
+    pub confidence: Vec<f32>,
+}
+
+/// A handle to the background capture thread spawned by [ArducamDepthCamera::into_stream].
+///
+/// Dropping the stream signals the capture thread to finish and joins it, which in turn drops
+/// (and stops/closes) the underlying camera. Call [FrameStream::stop] instead to recover the
+/// camera handle.
+pub struct FrameStream {
+    stop: Arc<AtomicBool>,
+    recycle: Sender<(Vec<f32>, Vec<f32>)>,
+    handle: Option<JoinHandle<ArducamDepthCamera>>,
+}
+
+impl FrameStream {
+    /// Return a drained frame's buffers to the capture thread for reuse.
+    ///
+    /// This closes the recycling loop that lets the capture thread avoid allocating a fresh pair
+    /// of `Vec`s for every frame.
+    pub fn recycle(&self, frame: OwnedFrame) {
+        let _ = self.recycle.send((frame.depth, frame.confidence));
+    }
+
+    /// Stop the capture thread and recover the camera handle.
+    pub fn stop(mut self) -> std::thread::Result<ArducamDepthCamera> {
+        self.stop.store(true, Ordering::Relaxed);
+        self.handle
+            .take()
+            .expect("handle is only taken here or in Drop, never both")
+            .join()
+    }
+}
+
+impl Drop for FrameStream {
+    fn drop(&mut self) {
+        self.stop.store(true, Ordering::Relaxed);
+        if let Some(handle) = self.handle.take() {
+            let _ = handle.join();
+        }
+    }
 }
 
 impl Drop for ArducamDepthCamera {
@@ -240,6 +471,12 @@ impl<'a, T: Copy> FrameData<'a, T> {
         &self.data
     }
 
+    /// Consume the handle and return the underlying row-major slice, tied to the lifetime of
+    /// the originating [ArducamFrameBuffer] rather than to this `FrameData`.
+    pub fn into_slice(self) -> &'a [T] {
+        self.data
+    }
+
     /// Get the width of the frame in pixels
     pub fn width(&self) -> u16 {
         self.width
@@ -332,6 +569,62 @@ make_enum_from_c! {
     invalid_type = pub struct InvalidConnectionType;
 }
 
+make_enum_from_c! {
+    /// A tunable sensor control, passed to [ArducamDepthCamera::set_control] and
+    /// [ArducamDepthCamera::get_control].
+    #[derive(Debug, Clone, Copy)]
+    pub enum Control: raw::Control {
+        /// The distance range, switching the sensor between its 2m and 4m modes.
+        Range => raw::Control_RANGE,
+        /// The capture frame rate in frames per second.
+        FrameRate => raw::Control_FRAME_RATE,
+        /// The exposure time. Only honoured while [AutoExposure](Control::AutoExposure) is off.
+        Exposure => raw::Control_EXPOSURE,
+        /// Pixels whose confidence falls below this value are discarded in hardware.
+        ConfidenceThreshold => raw::Control_CONFIDENCE_THRESHOLD,
+        /// Whether the sensor picks the exposure automatically (`1`) or not (`0`).
+        AutoExposure => raw::Control_AUTO_EXPOSURE,
+    }
+    #[derive(Debug, Error)]
+    #[error("Invalid control: {0}")]
+    invalid_type = pub struct InvalidControl;
+}
+
+/// The inclusive range of values a [Control] will accept.
+///
+/// Obtained from [Control::range], callers can use it to validate a value before
+/// handing it to [ArducamDepthCamera::set_control].
+#[derive(Debug, Clone, Copy)]
+pub struct ControlRange {
+    /// The smallest accepted value.
+    pub min: i32,
+    /// The largest accepted value.
+    pub max: i32,
+}
+
+impl ControlRange {
+    /// Returns `true` if `value` lies within `min..=max`.
+    pub fn contains(&self, value: i32) -> bool {
+        (self.min..=self.max).contains(&value)
+    }
+}
+
+impl Control {
+    /// The inclusive range of values this control accepts.
+    ///
+    /// The 0.1.3 SDK has no range query, so these mirror the documented hardware limits.
+    pub fn range(self) -> ControlRange {
+        match self {
+            // 0 => 2m mode, 1 => 4m mode
+            Control::Range => ControlRange { min: 0, max: 1 },
+            Control::FrameRate => ControlRange { min: 1, max: 30 },
+            Control::Exposure => ControlRange { min: 1, max: 2000 },
+            Control::ConfidenceThreshold => ControlRange { min: 0, max: 255 },
+            Control::AutoExposure => ControlRange { min: 0, max: 1 },
+        }
+    }
+}
+
 // 0.1.3 has no device type
 // make_enum_from_c! {
 //     #[derive(Debug)]
@@ -0,0 +1,123 @@
+//! Reprojection of depth frames into metric 3D points using a pinhole model.
+//!
+//! This factors out the `fx`/`fy` computation and per-pixel reprojection that the `point_cloud`
+//! and `point_cloud_client` examples previously open-coded from the same hardcoded field of view.
+
+use crate::ArducamFrameBuffer;
+
+/// The field of view of the camera, used to derive pinhole focal lengths.
+///
+/// Angles are stored in radians; use [CameraIntrinsics::from_degrees] to build one from the
+/// degree figures quoted on the datasheet.
+#[derive(Debug, Clone, Copy)]
+pub struct CameraIntrinsics {
+    /// Horizontal field of view, in radians.
+    pub hfov: f32,
+    /// Vertical field of view, in radians.
+    pub vfov: f32,
+}
+
+impl CameraIntrinsics {
+    /// The field of view of the Arducam ToF camera (64.3° × 50.4°).
+    pub const ARDUCAM_TOF: Self = Self {
+        hfov: 64.3 * std::f32::consts::PI / 180.0,
+        vfov: 50.4 * std::f32::consts::PI / 180.0,
+    };
+
+    /// Build intrinsics from a horizontal and vertical field of view given in degrees.
+    pub fn from_degrees(hfov: f32, vfov: f32) -> Self {
+        Self {
+            hfov: hfov * std::f32::consts::PI / 180.0,
+            vfov: vfov * std::f32::consts::PI / 180.0,
+        }
+    }
+
+    /// The pinhole focal lengths `(fx, fy)` for a frame of the given size.
+    ///
+    /// `fx = width / (2·tan(0.5·hfov))` and `fy = height / (2·tan(0.5·vfov))`.
+    pub fn focal_lengths(&self, width: u16, height: u16) -> (f32, f32) {
+        let fx = width as f32 / (2.0 * f32::tan(0.5 * self.hfov));
+        let fy = height as f32 / (2.0 * f32::tan(0.5 * self.vfov));
+        (fx, fy)
+    }
+
+    /// The principal point `(cx, cy)`, assumed to be the image centre.
+    pub fn principal_point(&self, width: u16, height: u16) -> (f32, f32) {
+        (width as f32 / 2.0, height as f32 / 2.0)
+    }
+}
+
+/// A single reprojected point, in the depth frame's units (millimetres), tagged with its source
+/// confidence.
+#[derive(Debug, Clone, Copy)]
+pub struct Point {
+    /// Horizontal (camera x) coordinate, positive for pixels left of the image centre.
+    pub x: f32,
+    /// Upward (camera y) coordinate.
+    pub y: f32,
+    /// Forward (camera z) coordinate, i.e. the depth.
+    pub z: f32,
+    /// The confidence reported for the source pixel.
+    pub confidence: f32,
+}
+
+impl ArducamFrameBuffer<'_> {
+    /// Reproject the depth frame into a point cloud using the pinhole model.
+    ///
+    /// For a pixel `(col, row)` at depth `z`, `x = ((width/2 − col)/fx)·z` and
+    /// `y = ((height/2 − row)/fy)·z`, with `fx`/`fy` from [CameraIntrinsics::focal_lengths].
+    /// Pixels whose depth is `0` or `NaN` (invalid ToF returns) are skipped, as are pixels below
+    /// `confidence_threshold` when it is `Some`.
+    pub fn point_cloud(
+        &self,
+        intrinsics: &CameraIntrinsics,
+        confidence_threshold: Option<f32>,
+    ) -> Vec<Point> {
+        self.point_cloud_iter(intrinsics, confidence_threshold)
+            .collect()
+    }
+
+    /// An allocation-free iterator over the reprojected point cloud.
+    ///
+    /// See [point_cloud](ArducamFrameBuffer::point_cloud) for the projection details.
+    pub fn point_cloud_iter<'b>(
+        &'b self,
+        intrinsics: &CameraIntrinsics,
+        confidence_threshold: Option<f32>,
+    ) -> impl Iterator<Item = Point> + 'b {
+        let depth = self.get_depth_data();
+        let confidence = self.get_confidence_data();
+
+        let width = depth.width();
+        let height = depth.height();
+        let (fx, fy) = intrinsics.focal_lengths(width, height);
+
+        let depth = depth.into_slice();
+        let confidence = confidence.into_slice();
+
+        depth
+            .iter()
+            .zip(confidence)
+            .enumerate()
+            .filter_map(move |(i, (&z, &c))| {
+                if z == 0.0 || z.is_nan() {
+                    return None;
+                }
+                if confidence_threshold.is_some_and(|threshold| c < threshold) {
+                    return None;
+                }
+
+                let col = (i % width as usize) as f32;
+                let row = (i / width as usize) as f32;
+                let x = ((width as f32 / 2.0 - col) / fx) * z;
+                let y = ((height as f32 / 2.0 - row) / fy) * z;
+
+                Some(Point {
+                    x,
+                    y,
+                    z,
+                    confidence: c,
+                })
+            })
+    }
+}
@@ -0,0 +1,114 @@
+//! Optional [`rerun`](https://rerun.io) logging for depth frames and point clouds.
+//!
+//! Enabled by the `rerun` cargo feature. These helpers log an [ArducamFrameBuffer] to a
+//! [`rerun::RecordingStream`] so users get time-synced 2D + 3D visualisation and recording
+//! without hand-rolling a renderer.
+
+use rerun::external::ndarray;
+use thiserror::Error;
+
+use crate::{ArducamFrameBuffer, CameraIntrinsics};
+
+#[derive(Debug, Error)]
+/// Returned when logging a frame to a [`rerun::RecordingStream`] fails.
+pub enum RerunError {
+    /// A logged image could not be constructed from the frame data.
+    #[error("failed to build image for rerun: {0}")]
+    Image(#[from] rerun::ImageConstructionError<ndarray::Array2<f32>>),
+    /// The recording stream rejected a log call.
+    #[error("failed to log to rerun: {0}")]
+    Log(#[from] rerun::RecordingStreamError),
+}
+
+/// Log a `Pinhole` transform derived from `intrinsics` for a frame of the given size.
+///
+/// Pairing this with a `DepthImage` logged at a child entity path lets the Rerun viewer perform
+/// the depth backprojection and interactive range clipping itself, rather than streaming
+/// pre-projected points.
+pub fn log_pinhole_to_rerun(
+    rec: &rerun::RecordingStream,
+    entity_path: &str,
+    intrinsics: &CameraIntrinsics,
+    width: u16,
+    height: u16,
+) -> Result<(), RerunError> {
+    let (fx, fy) = intrinsics.focal_lengths(width, height);
+    let pinhole = rerun::Pinhole::from_focal_length_and_resolution(
+        [fx, fy],
+        [width as f32, height as f32],
+    );
+    rec.log(entity_path, &pinhole)?;
+    Ok(())
+}
+
+impl ArducamFrameBuffer<'_> {
+    /// Log the depth frame as a `DepthImage` to `entity_path`.
+    pub fn log_depth_to_rerun(
+        &self,
+        rec: &rerun::RecordingStream,
+        entity_path: &str,
+    ) -> Result<(), RerunError> {
+        let depth = self.get_depth_data();
+        let array = ndarray::Array2::from_shape_vec(
+            (depth.height() as usize, depth.width() as usize),
+            depth.as_slice().to_vec(),
+        )
+        .expect("depth buffer length matches width × height");
+        rec.log(entity_path, &rerun::DepthImage::try_from(array)?)?;
+        Ok(())
+    }
+
+    /// Log the confidence frame as a grayscale image to `entity_path`.
+    pub fn log_confidence_to_rerun(
+        &self,
+        rec: &rerun::RecordingStream,
+        entity_path: &str,
+    ) -> Result<(), RerunError> {
+        let confidence = self.get_confidence_data();
+        let array = ndarray::Array2::from_shape_vec(
+            (confidence.height() as usize, confidence.width() as usize),
+            confidence.as_slice().iter().map(|c| *c as u8).collect(),
+        )
+        .expect("confidence buffer length matches width × height");
+        rec.log(entity_path, &rerun::Image::from_l8(array))?;
+        Ok(())
+    }
+
+    /// Log the reprojected point cloud as `Points3D` with per-point colours derived from
+    /// confidence.
+    pub fn log_point_cloud_to_rerun(
+        &self,
+        rec: &rerun::RecordingStream,
+        entity_path: &str,
+        intrinsics: &CameraIntrinsics,
+    ) -> Result<(), RerunError> {
+        let mut positions = Vec::new();
+        let mut colors = Vec::new();
+        for point in self.point_cloud_iter(intrinsics, None) {
+            positions.push((point.x, point.y, point.z));
+            let level = point.confidence.clamp(0.0, 255.0) as u8;
+            colors.push(rerun::Color::from_rgb(level, level, level));
+        }
+        rec.log(
+            entity_path,
+            &rerun::Points3D::new(positions).with_colors(colors),
+        )?;
+        Ok(())
+    }
+
+    /// Log the depth image, confidence image, and reprojected point cloud in one call.
+    ///
+    /// The depth image is logged at `entity_path`, with the confidence image and point cloud
+    /// logged at `{entity_path}/confidence` and `{entity_path}/points` respectively.
+    pub fn log_to_rerun(
+        &self,
+        rec: &rerun::RecordingStream,
+        entity_path: &str,
+        intrinsics: &CameraIntrinsics,
+    ) -> Result<(), RerunError> {
+        self.log_depth_to_rerun(rec, entity_path)?;
+        self.log_confidence_to_rerun(rec, &format!("{entity_path}/confidence"))?;
+        self.log_point_cloud_to_rerun(rec, &format!("{entity_path}/points"), intrinsics)?;
+        Ok(())
+    }
+}